@@ -1,5 +1,4 @@
-use taml::{parse, stringify, validate, Value};
-use std::collections::HashMap;
+use taml::{parse, stringify, validate, Map, Value};
 
 fn main() {
     println!("TAML Rust Library Examples\n");
@@ -57,7 +56,7 @@ features
 
     // Example 2: Creating and Serializing TAML
     println!("\n=== Example 2: Creating and Serializing TAML ===");
-    let mut data = HashMap::new();
+    let mut data = Map::new();
     data.insert("name".to_string(), Value::String("Test App".to_string()));
     data.insert("version".to_string(), Value::String("2.0.0".to_string()));
     data.insert("enabled".to_string(), Value::Boolean(true));
@@ -65,7 +64,7 @@ features
     data.insert("timeout".to_string(), Value::Float(30.5));
     data.insert("api_key".to_string(), Value::Null);
     
-    let mut config = HashMap::new();
+    let mut config = Map::new();
     config.insert("debug".to_string(), Value::Boolean(true));
     config.insert("log_level".to_string(), Value::String("info".to_string()));
     data.insert("config".to_string(), Value::Object(config));
@@ -0,0 +1,400 @@
+//! A first-class date/time value, modeled after the TOML crate's `Datetime`
+//!
+//! TAML scalars are just text, so a timestamp like `2024-01-02T03:04:05Z`
+//! would otherwise collapse into a plain `Value::String` and lose its type.
+//! `Datetime` keeps the date, time and offset components (each optional, so
+//! a bare date or a bare time is representable) and displays in a canonical
+//! RFC 3339 form that parses back to an equal value. That form is not always
+//! the source text itself: a space or lowercase `t` separator is written as
+//! `T`, a lowercase `z` as `Z`, trailing zeros of fractional seconds are
+//! dropped, and fractional digits past the ninth are truncated.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A TAML date-time, date, or time value.
+///
+/// At least one of `date` or `time` is always present; `offset` only makes
+/// sense alongside a `time`. `PartialEq`/`Ord`/`Hash` are implemented by
+/// hand rather than derived: they compare the UTC-normalized `(date, time)`
+/// this value represents (see [`Datetime::normalized`]), so two values
+/// written with different offsets but naming the same instant — or the
+/// same instant expressed on either side of a calendar-day rollover —
+/// compare and hash equal, and sort chronologically.
+#[derive(Debug, Clone, Copy)]
+pub struct Datetime {
+    /// The calendar date, if present.
+    pub date: Option<Date>,
+    /// The time of day, if present.
+    pub time: Option<Time>,
+    /// The UTC offset, if present (only meaningful when `time` is present).
+    pub offset: Option<Offset>,
+}
+
+/// A calendar date (`YYYY-MM-DD`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// A time of day (`HH:MM:SS[.fff]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// A UTC offset, either `Z` or `+HH:MM` / `-HH:MM`.
+///
+/// `PartialEq`/`Eq`/`Hash` compare by effective distance from UTC (see
+/// [`Offset::as_minutes`]) rather than by variant, matching `Ord` below, so
+/// `Z` and `Custom { minutes: 0 }` are equal under all four traits —
+/// otherwise a `BTreeSet`/`HashSet` could treat them as equal for ordering
+/// purposes but distinct for dedup, which is exactly backwards.
+#[derive(Debug, Clone, Copy)]
+pub enum Offset {
+    /// `Z`, i.e. UTC.
+    Z,
+    /// An explicit offset, in minutes from UTC.
+    Custom { minutes: i16 },
+}
+
+impl Offset {
+    /// This offset's distance from UTC, in minutes.
+    fn as_minutes(self) -> i16 {
+        match self {
+            Offset::Z => 0,
+            Offset::Custom { minutes } => minutes,
+        }
+    }
+}
+
+impl PartialEq for Offset {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_minutes() == other.as_minutes()
+    }
+}
+
+impl Eq for Offset {}
+
+impl std::hash::Hash for Offset {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_minutes().hash(state);
+    }
+}
+
+impl PartialOrd for Offset {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Offset {
+    /// Compares by effective distance from UTC, so `Z` and `+00:00` are
+    /// equal in ordering even though they're different variants.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_minutes().cmp(&other.as_minutes())
+    }
+}
+
+impl Datetime {
+    /// The `(date, time)` this value represents in UTC, with `offset`
+    /// folded in (and dropped, since a UTC-normalized value has none).
+    ///
+    /// `PartialEq`, `Ord`, and `Hash` all key off this, so instants that
+    /// were written with different offsets compare equal and sort
+    /// chronologically, including across a calendar-day rollover caused by
+    /// the offset shift (e.g. `2024-01-02T23:30:00-01:00` is
+    /// `2024-01-03T00:30:00Z`).
+    fn normalized(&self) -> (Option<Date>, Option<Time>) {
+        let (Some(time), Some(offset)) = (self.time, self.offset) else {
+            return (self.date, self.time);
+        };
+
+        let minute_of_day = time.hour as i64 * 60 + time.minute as i64 - offset.as_minutes() as i64;
+        let day_delta = minute_of_day.div_euclid(1440);
+        let minute_of_day = minute_of_day.rem_euclid(1440);
+
+        let normalized_time = Time {
+            hour: (minute_of_day / 60) as u8,
+            minute: (minute_of_day % 60) as u8,
+            second: time.second,
+            nanosecond: time.nanosecond,
+        };
+
+        let normalized_date = self.date.map(|date| {
+            let days = days_from_civil(date.year as i64, date.month as i64, date.day as i64);
+            let (year, month, day) = civil_from_days(days + day_delta);
+            Date {
+                year: year as u16,
+                month: month as u8,
+                day: day as u8,
+            }
+        });
+
+        (normalized_date, Some(normalized_time))
+    }
+}
+
+impl PartialEq for Datetime {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl Eq for Datetime {}
+
+impl std::hash::Hash for Datetime {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.normalized().hash(state);
+    }
+}
+
+impl PartialOrd for Datetime {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Datetime {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.normalized().cmp(&other.normalized())
+    }
+}
+
+/// Days since the Unix epoch for a proleptic-Gregorian civil date, via
+/// Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400; // [0, 399]
+    let mp = (m + 9) % 12; // [0, 11]
+    let doy = (153 * mp + 2) / 5 + d - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719468;
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe = z - era * 146097; // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let d = doy - (153 * mp + 2) / 5 + 1; // [1, 31]
+    let m = if mp < 10 { mp + 3 } else { mp - 9 }; // [1, 12]
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond != 0 {
+            let mut fraction = format!("{:09}", self.nanosecond);
+            while fraction.ends_with('0') {
+                fraction.pop();
+            }
+            write!(f, ".{}", fraction)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Offset::Z => write!(f, "Z"),
+            Offset::Custom { minutes } => {
+                let sign = if *minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Datetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.date, &self.time) {
+            (Some(date), Some(time)) => {
+                write!(f, "{}T{}", date, time)?;
+            }
+            (Some(date), None) => write!(f, "{}", date)?,
+            (None, Some(time)) => write!(f, "{}", time)?,
+            (None, None) => {}
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, "{}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Datetime {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Full date-time: `<date>[T ]<time>[offset]`
+        if s.len() > 10 && matches!(s.as_bytes()[10], b'T' | b't' | b' ') {
+            let date = parse_date(&s[..10]).ok_or(())?;
+            let (time, offset) = parse_time_with_offset(&s[11..]).ok_or(())?;
+            return Ok(Datetime {
+                date: Some(date),
+                time: Some(time),
+                offset,
+            });
+        }
+
+        // Date only.
+        if let Some(date) = parse_date(s) {
+            return Ok(Datetime {
+                date: Some(date),
+                time: None,
+                offset: None,
+            });
+        }
+
+        // Time only.
+        if let Some((time, offset)) = parse_time_with_offset(s) {
+            return Ok(Datetime {
+                date: None,
+                time: Some(time),
+                offset,
+            });
+        }
+
+        Err(())
+    }
+}
+
+fn parse_date(s: &str) -> Option<Date> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let year = s.get(0..4)?.parse().ok()?;
+    let month = s.get(5..7)?.parse().ok()?;
+    let day = s.get(8..10)?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(Date { year, month, day })
+}
+
+fn parse_time_with_offset(s: &str) -> Option<(Time, Option<Offset>)> {
+    if let Some(rest) = s.strip_suffix('Z').or_else(|| s.strip_suffix('z')) {
+        return Some((parse_time(rest)?, Some(Offset::Z)));
+    }
+
+    // Look for a trailing `+HH:MM` / `-HH:MM` offset (but not the leading
+    // sign-less seconds separator).
+    if s.len() > 6 {
+        let tail = &s[s.len() - 6..];
+        let tail_bytes = tail.as_bytes();
+        if matches!(tail_bytes[0], b'+' | b'-') && tail_bytes[3] == b':' {
+            let time = parse_time(&s[..s.len() - 6])?;
+            let sign: i16 = if tail_bytes[0] == b'-' { -1 } else { 1 };
+            let hours: i16 = tail.get(1..3)?.parse().ok()?;
+            let minutes: i16 = tail.get(4..6)?.parse().ok()?;
+            return Some((
+                time,
+                Some(Offset::Custom {
+                    minutes: sign * (hours * 60 + minutes),
+                }),
+            ));
+        }
+    }
+
+    parse_time(s).map(|time| (time, None))
+}
+
+fn parse_time(s: &str) -> Option<Time> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 8 || bytes[2] != b':' || bytes[5] != b':' {
+        return None;
+    }
+    let hour: u8 = s.get(0..2)?.parse().ok()?;
+    let minute: u8 = s.get(3..5)?.parse().ok()?;
+    let second: u8 = s.get(6..8)?.parse().ok()?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let nanosecond = if s.len() > 8 {
+        let rest = s.get(8..)?;
+        let fraction = rest.strip_prefix('.')?;
+        if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut digits = fraction.to_string();
+        digits.truncate(9);
+        while digits.len() < 9 {
+            digits.push('0');
+        }
+        digits.parse().ok()?
+    } else {
+        0
+    };
+
+    Some(Time {
+        hour,
+        minute,
+        second,
+        nanosecond,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datetimes_compare_chronologically() {
+        let earlier = Datetime::from_str("2024-01-02T03:04:05Z").unwrap();
+        let later = Datetime::from_str("2024-01-02T03:04:06Z").unwrap();
+        assert!(earlier < later);
+    }
+
+    #[test]
+    fn test_z_and_equivalent_numeric_offset_compare_equal() {
+        assert_eq!(Offset::Z.cmp(&Offset::Custom { minutes: 0 }), std::cmp::Ordering::Equal);
+        assert_eq!(Offset::Z, Offset::Custom { minutes: 0 });
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(Offset::Z);
+        assert!(!set.insert(Offset::Custom { minutes: 0 }));
+    }
+
+    #[test]
+    fn test_datetimes_compare_chronologically_across_offsets() {
+        // 22:00 UTC, earlier than...
+        let earlier = Datetime::from_str("2024-01-02T23:00:00+01:00").unwrap();
+        // ...22:30 UTC.
+        let later = Datetime::from_str("2024-01-02T22:30:00Z").unwrap();
+        assert!(earlier < later);
+    }
+
+    #[test]
+    fn test_datetimes_with_different_offsets_naming_the_same_instant_are_equal() {
+        let a = Datetime::from_str("2024-01-02T23:30:00-01:00").unwrap();
+        let b = Datetime::from_str("2024-01-03T00:30:00Z").unwrap();
+        assert_eq!(a, b);
+        assert!(a.cmp(&b) == std::cmp::Ordering::Equal);
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(a);
+        assert!(!set.insert(b));
+    }
+}
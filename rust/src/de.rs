@@ -0,0 +1,446 @@
+//! Serde `Deserializer` that walks an already-parsed `Value` tree
+//!
+//! This is the read half of the serde integration: it lets callers do
+//! `let cfg: MyStruct = taml::from_str(text)?` instead of pulling fields out
+//! of a `Value` by hand.
+//!
+//! Typed entry points (`deserialize_bool`, `deserialize_i64`, ...) parse the
+//! underlying scalar directly rather than going through `deserialize_any`,
+//! so a schema mismatch (e.g. a struct field typed `bool` backed by a
+//! TAML value of `maybe`) is reported as a real error instead of silently
+//! falling back to a string. Only `deserialize_any` uses the value's own
+//! type to decide which `visit_*` to call.
+//!
+//! Typed mismatches carry a message but no source line: `Deserializer`
+//! walks an already-parsed [`Value`], which has no span attached to its
+//! scalars, so there is nothing to report a line number from.
+//! [`crate::parse_spanned`] does record line/column spans, but it is not
+//! connected to this `Deserializer`: a [`crate::Spanned`] field deserialized
+//! here gets a zeroed span.
+
+use serde::de::{self, Deserialize, IntoDeserializer};
+
+use crate::error::{TAMLError, TAMLResult};
+use crate::parser::parse;
+use crate::value::Value;
+
+/// Parse TAML text and deserialize it into `T`.
+pub fn from_str<'de, T>(text: &str) -> TAMLResult<T>
+where
+    T: Deserialize<'de>,
+{
+    let value = parse(text)?;
+    from_value(&value)
+}
+
+/// Deserialize `T` from an already-parsed `Value`.
+pub fn from_value<'de, T>(value: &Value) -> TAMLResult<T>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(Deserializer { value })
+}
+
+/// A serde `Deserializer` backed by a reference into a parsed `Value` tree.
+pub struct Deserializer<'a> {
+    value: &'a Value,
+}
+
+impl<'a> Deserializer<'a> {
+    /// Wrap a `Value` for use as a serde deserializer.
+    pub fn new(value: &'a Value) -> Self {
+        Deserializer { value }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Deserializer<'a> {
+    type Error = TAMLError;
+
+    fn deserialize_any<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            Value::Boolean(b) => visitor.visit_bool(*b),
+            Value::Integer(i) => visitor.visit_i64(*i),
+            Value::Float(f) => visitor.visit_f64(*f),
+            Value::String(s) => visitor.visit_str(s),
+            Value::Datetime(dt) => visitor.visit_string(dt.to_string()),
+            Value::Array(arr) => visitor.visit_seq(SeqDeserializer { iter: arr.iter() }),
+            Value::Object(obj) => visitor.visit_map(MapDeserializer {
+                iter: obj.iter(),
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::String(s) => visitor.visit_enum(s.clone().into_deserializer()),
+            Value::Object(obj) if obj.len() == 1 => {
+                let (variant, value) = obj.iter().next().unwrap();
+                visitor.visit_enum(de::value::MapAccessDeserializer::new(MapDeserializer {
+                    iter: std::iter::once((variant, value)),
+                    value: None,
+                }))
+            }
+            _ => Err(TAMLError::new("expected a string or single-key object for an enum")),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_bool(self.expect_bool()?)
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i64(self.expect_integer()?)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i64(self.expect_integer()?)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i64(self.expect_integer()?)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i64(self.expect_integer()?)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i128(self.expect_integer()? as i128)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u64(self.expect_unsigned()?)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u64(self.expect_unsigned()?)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u64(self.expect_unsigned()?)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u64(self.expect_unsigned()?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u128(self.expect_unsigned()? as u128)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(self.expect_float()?)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(self.expect_float()?)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let s = self.expect_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(TAMLError::new(format!(
+                "expected a single character, found \"{}\"",
+                s
+            ))),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_str(&self.expect_str()?)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_string(self.expect_str()?.into_owned())
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            other => Err(TAMLError::new(format!("expected null, found {:?}", other))),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Array(arr) => visitor.visit_seq(SeqDeserializer { iter: arr.iter() }),
+            other => Err(TAMLError::new(format!("expected an array, found {:?}", other))),
+        }
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Object(obj) => visitor.visit_map(MapDeserializer {
+                iter: obj.iter(),
+                value: None,
+            }),
+            other => Err(TAMLError::new(format!("expected an object, found {:?}", other))),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> TAMLResult<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bytes byte_buf unit_struct newtype_struct tuple
+        tuple_struct identifier ignored_any
+    }
+}
+
+impl<'a> Deserializer<'a> {
+    fn expect_bool(&self) -> TAMLResult<bool> {
+        match self.value {
+            Value::Boolean(b) => Ok(*b),
+            Value::String(s) => s
+                .parse()
+                .map_err(|_| TAMLError::new(format!("expected a boolean, found {:?}", self.value))),
+            other => Err(TAMLError::new(format!("expected a boolean, found {:?}", other))),
+        }
+    }
+
+    fn expect_integer(&self) -> TAMLResult<i64> {
+        match self.value {
+            Value::Integer(i) => Ok(*i),
+            Value::String(s) => s
+                .parse()
+                .map_err(|_| TAMLError::new(format!("expected an integer, found {:?}", self.value))),
+            other => Err(TAMLError::new(format!("expected an integer, found {:?}", other))),
+        }
+    }
+
+    fn expect_unsigned(&self) -> TAMLResult<u64> {
+        let i = self.expect_integer()?;
+        u64::try_from(i).map_err(|_| TAMLError::new(format!("expected an unsigned integer, found {}", i)))
+    }
+
+    fn expect_float(&self) -> TAMLResult<f64> {
+        match self.value {
+            Value::Float(f) => Ok(*f),
+            Value::Integer(i) => Ok(*i as f64),
+            Value::String(s) => s
+                .parse()
+                .map_err(|_| TAMLError::new(format!("expected a float, found {:?}", self.value))),
+            other => Err(TAMLError::new(format!("expected a float, found {:?}", other))),
+        }
+    }
+
+    fn expect_str(&self) -> TAMLResult<std::borrow::Cow<'a, str>> {
+        match self.value {
+            Value::String(s) => Ok(std::borrow::Cow::Borrowed(s)),
+            // A `String`-typed field backed by a date-shaped scalar (e.g.
+            // `created\t2024-01-02`) got auto-promoted to `Value::Datetime`
+            // by the parser's type inference; fall back to its rendered
+            // text rather than rejecting a document that round-trips fine
+            // through `deserialize_any`.
+            Value::Datetime(dt) => Ok(std::borrow::Cow::Owned(dt.to_string())),
+            other => Err(TAMLError::new(format!("expected a string, found {:?}", other))),
+        }
+    }
+}
+
+struct SeqDeserializer<'a> {
+    iter: std::slice::Iter<'a, Value>,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for SeqDeserializer<'a> {
+    type Error = TAMLError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> TAMLResult<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(Deserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct MapDeserializer<'a, I> {
+    iter: I,
+    value: Option<&'a Value>,
+}
+
+impl<'de, 'a, I> de::MapAccess<'de> for MapDeserializer<'a, I>
+where
+    I: Iterator<Item = (&'a String, &'a Value)>,
+{
+    type Error = TAMLError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> TAMLResult<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.clone().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> TAMLResult<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| TAMLError::new("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(Deserializer { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+        active: bool,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        application: String,
+        server: Server,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_from_str_deserializes_nested_struct() {
+        let taml = "application\tMyApp\nserver\n\thost\tlocalhost\n\tport\t8080\n\tactive\ttrue\ntags\n\tweb\n\tprod";
+        let config: Config = from_str(taml).unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                application: "MyApp".to_string(),
+                server: Server {
+                    host: "localhost".to_string(),
+                    port: 8080,
+                    active: true,
+                },
+                tags: vec!["web".to_string(), "prod".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_typed_field_mismatch_is_a_descriptive_error() {
+        let taml = "server\n\thost\tlocalhost\n\tport\tnotaport\n\tactive\ttrue";
+        let err = from_str::<Config>(taml).unwrap_err();
+        assert!(err.to_string().contains("expected an integer"));
+    }
+
+    #[test]
+    fn test_string_field_accepts_a_date_shaped_scalar() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Entry {
+            created: String,
+        }
+
+        let entry: Entry = from_str("created\t2024-01-02").unwrap();
+        assert_eq!(
+            entry,
+            Entry {
+                created: "2024-01-02".to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! TAML Document - a format-preserving edit model
+//!
+//! `parse`/`Value` is a lossy, one-way read: comments and blank lines are
+//! discarded and there's no way to write a document back out. `Document`
+//! keeps those around so a tool can load a TAML file, tweak one value, and
+//! write it back with every other entry, comment and blank line in place.
+//!
+//! Whitespace is not kept byte-for-byte: a run of tabs between a key and its
+//! value, or before a trailing comment, is written back as a single tab;
+//! trailing whitespace after a key or value is dropped; a comment line is
+//! re-indented to its entry's level; a whitespace-only line is written back
+//! empty; and lines are joined with `\n`, with no final newline.
+//!
+//! A trailing inline comment on an entry's own line is written as a
+//! tab-separated `#` segment, e.g. `host\tlocalhost\t# falls back to this`,
+//! so it can't be confused with a `#` that's merely part of a value.
+//!
+//! Space-indented lines are invalid TAML (see [`crate::validate`]), but are
+//! still kept verbatim as [`DocEntry::leading_invalid_lines`] of the entry
+//! that follows rather than dropped. Lines indented deeper than their
+//! enclosing key expects (e.g. an orphaned child of a leaf value) are kept
+//! the same way, re-rendered from their parsed keys and values, so the
+//! whitespace notes above apply to them. The one gap: a comment, blank line,
+//! or invalid line/subtree with no following entry to attach to (i.e.
+//! trailing the whole document) has nothing to be attached to and is not
+//! retained.
+
+use std::fmt;
+
+use crate::constants::TAB;
+use crate::error::TAMLResult;
+
+/// A format-preserving TAML document.
+///
+/// Unlike [`crate::Value`], a `Document` retains comments, blank-line
+/// gaps, and the original (pre-type-conversion) text of every value, so
+/// `to_string()` reproduces untouched parts of the source, up to the
+/// whitespace normalization described in the module docs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    /// Top-level entries, in document order.
+    pub entries: Vec<DocEntry>,
+}
+
+/// A single key (and, for leaves, value) within a [`Document`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocEntry {
+    /// `#` comment lines immediately preceding this entry.
+    pub leading_comments: Vec<String>,
+    /// Lines immediately preceding this entry that this model couldn't
+    /// interpret: space-indented lines, which [`crate::validate`] already
+    /// flags as invalid, kept verbatim; and lines indented deeper than their
+    /// enclosing key expects, re-rendered from their parsed keys and values.
+    /// `to_string()` writes them back even though they carry no structure
+    /// of their own.
+    pub leading_invalid_lines: Vec<String>,
+    /// Number of blank lines between the previous entry and this one.
+    pub blank_lines_before: usize,
+    /// The entry's key.
+    pub key: String,
+    /// The original, pre-type-conversion text of the value, if this is a
+    /// leaf entry. `None` for a parent (object) entry.
+    pub raw_value: Option<String>,
+    /// A `#` comment trailing this entry's own line (key line for a parent
+    /// entry, key-value line for a leaf), if any. Includes the leading `#`.
+    pub trailing_comment: Option<String>,
+    /// Nested entries, for a parent (object) entry.
+    pub children: Vec<DocEntry>,
+}
+
+impl DocEntry {
+    fn new(key: String) -> Self {
+        DocEntry {
+            leading_comments: Vec::new(),
+            leading_invalid_lines: Vec::new(),
+            blank_lines_before: 0,
+            key,
+            raw_value: None,
+            trailing_comment: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Parse a TAML string into a format-preserving [`Document`].
+pub fn parse_document(text: &str) -> TAMLResult<Document> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut raw_entries = Vec::new();
+    let mut pending_comments = Vec::new();
+    let mut pending_invalid = Vec::new();
+    let mut pending_blanks = 0;
+
+    for line in &lines {
+        if line.trim().is_empty() {
+            pending_blanks += 1;
+            continue;
+        }
+
+        if line.trim_start().starts_with('#') {
+            pending_comments.push(line.trim_start().to_string());
+            continue;
+        }
+
+        if line.starts_with(' ') {
+            // Space-indented lines are invalid TAML (see `crate::validate`)
+            // and carry no structure this model can place in the tree, but
+            // they still need to round-trip: stash the line verbatim and
+            // attach it to whichever entry follows.
+            pending_invalid.push(line.to_string());
+            continue;
+        }
+
+        let level = line.chars().take_while(|&c| c == TAB).count();
+        let content = &line[level..];
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        let (content, trailing_comment) = match content.find("\t#") {
+            Some(idx) => (&content[..idx], Some(content[idx + 1..].to_string())),
+            None => (content, None),
+        };
+
+        let tab_index = content.find(TAB);
+        let (key, raw_value) = if let Some(idx) = tab_index {
+            let key = &content[..idx];
+            let value_start = idx + content[idx..].chars().take_while(|&c| c == TAB).count();
+            let raw = if value_start < content.len() {
+                content[value_start..].trim_end()
+            } else {
+                ""
+            };
+            (key, Some(raw.to_string()))
+        } else {
+            (content.trim_end(), None)
+        };
+
+        let mut entry = DocEntry::new(key.to_string());
+        entry.raw_value = raw_value;
+        entry.trailing_comment = trailing_comment;
+        entry.leading_comments = std::mem::take(&mut pending_comments);
+        entry.leading_invalid_lines = std::mem::take(&mut pending_invalid);
+        entry.blank_lines_before = std::mem::take(&mut pending_blanks);
+        raw_entries.push((level, entry));
+    }
+
+    let (entries, _) = build_tree(&raw_entries, 0, 0);
+    Ok(Document { entries })
+}
+
+fn build_tree(raw: &[(usize, DocEntry)], start: usize, level: usize) -> (Vec<DocEntry>, usize) {
+    let mut entries = Vec::new();
+    let mut i = start;
+    let mut pending_orphan_lines: Vec<String> = Vec::new();
+
+    while i < raw.len() && raw[i].0 >= level {
+        if raw[i].0 > level {
+            // A line indented deeper than this level expects, with no
+            // enclosing key to attach it to. `Document` is the lossless
+            // path, so unlike the plain parser this must not drop it:
+            // rebuild it (and any children of its own) as its own subtree,
+            // render that back to text, and stash it to be attached as
+            // leading, verbatim content on whichever entry follows — the
+            // same mechanism already used for space-indented lines.
+            let (orphan, next) = build_tree(raw, i, raw[i].0);
+            write_entries(&orphan, &mut pending_orphan_lines, raw[i].0);
+            i = next;
+            continue;
+        }
+
+        let mut entry = raw[i].1.clone();
+        let mut j = i + 1;
+        if entry.raw_value.is_none() {
+            let (children, next) = build_tree(raw, j, level + 1);
+            entry.children = children;
+            j = next;
+        }
+        if !pending_orphan_lines.is_empty() {
+            let mut lines = std::mem::take(&mut pending_orphan_lines);
+            lines.append(&mut entry.leading_invalid_lines);
+            entry.leading_invalid_lines = lines;
+        }
+        entries.push(entry);
+        i = j;
+    }
+
+    (entries, i)
+}
+
+impl Document {
+    /// Get the raw text of a value by key, or by a dotted path such as
+    /// `server.host` for a nested entry.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        find(&self.entries, key).and_then(|e| e.raw_value.as_deref())
+    }
+
+    /// Set the raw text of a value by key or dotted path (as for
+    /// [`Document::get`]), leaving every other entry unchanged. Does nothing if the key doesn't exist or names
+    /// a parent entry rather than a leaf.
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        if let Some(entry) = find_mut(&mut self.entries, key) {
+            if entry.raw_value.is_some() {
+                entry.raw_value = Some(value.into());
+            }
+        }
+    }
+
+}
+
+impl fmt::Display for Document {
+    /// Re-emit the document as TAML text, preserving comments, blank-line
+    /// gaps, and original tab indentation for every untouched entry.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        write_entries(&self.entries, &mut lines, 0);
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+fn find<'a>(entries: &'a [DocEntry], key: &str) -> Option<&'a DocEntry> {
+    let (head, rest) = split_path(key);
+    let entry = entries.iter().find(|e| e.key == head)?;
+    match rest {
+        Some(rest) => find(&entry.children, rest),
+        None => Some(entry),
+    }
+}
+
+fn find_mut<'a>(entries: &'a mut [DocEntry], key: &str) -> Option<&'a mut DocEntry> {
+    let (head, rest) = split_path(key);
+    let entry = entries.iter_mut().find(|e| e.key == head)?;
+    match rest {
+        Some(rest) => find_mut(&mut entry.children, rest),
+        None => Some(entry),
+    }
+}
+
+fn split_path(key: &str) -> (&str, Option<&str>) {
+    match key.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (key, None),
+    }
+}
+
+fn write_entries(entries: &[DocEntry], lines: &mut Vec<String>, level: usize) {
+    let indent = TAB.to_string().repeat(level);
+
+    for entry in entries {
+        for _ in 0..entry.blank_lines_before {
+            lines.push(String::new());
+        }
+        for invalid in &entry.leading_invalid_lines {
+            // Verbatim: these already include their own (invalid) leading
+            // whitespace, unlike comments which are re-indented below.
+            lines.push(invalid.clone());
+        }
+        for comment in &entry.leading_comments {
+            lines.push(format!("{}{}", indent, comment));
+        }
+
+        let comment_suffix = match &entry.trailing_comment {
+            Some(comment) => format!("\t{}", comment),
+            None => String::new(),
+        };
+
+        match &entry.raw_value {
+            Some(value) => lines.push(format!(
+                "{}{}\t{}{}",
+                indent, entry.key, value, comment_suffix
+            )),
+            None => {
+                lines.push(format!("{}{}{}", indent, entry.key, comment_suffix));
+                write_entries(&entry.children, lines, level + 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_preserves_comments_and_blank_lines() {
+        let taml = "# app config\napplication\tMyApp\n\nserver\n\thost\tlocalhost";
+        let doc = parse_document(taml).unwrap();
+        assert_eq!(doc.to_string(), taml);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_space_indented_lines_verbatim() {
+        let taml = "application\tMyApp\n  legacy\tvalue\nserver\n\thost\tlocalhost";
+        let doc = parse_document(taml).unwrap();
+        assert_eq!(doc.to_string(), taml);
+
+        let server = &doc.entries[1];
+        assert_eq!(server.leading_invalid_lines, vec!["  legacy\tvalue".to_string()]);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_orphaned_deep_indentation() {
+        let taml = "key\tvalue\n\t\tghost\tdata\nother\tvalue2";
+        let doc = parse_document(taml).unwrap();
+        assert_eq!(doc.to_string(), taml);
+
+        let other = &doc.entries[1];
+        assert_eq!(other.key, "other");
+        assert_eq!(other.leading_invalid_lines, vec!["\t\tghost\tdata".to_string()]);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_trailing_comments() {
+        let taml = "server\t# the only server we talk to\n\thost\tlocalhost\t# dev default";
+        let doc = parse_document(taml).unwrap();
+        assert_eq!(doc.to_string(), taml);
+
+        let server = &doc.entries[0];
+        assert_eq!(
+            server.trailing_comment.as_deref(),
+            Some("# the only server we talk to")
+        );
+        assert_eq!(
+            server.children[0].trailing_comment.as_deref(),
+            Some("# dev default")
+        );
+    }
+
+    #[test]
+    fn test_get_and_set_leaf_value() {
+        let taml = "server\n\thost\tlocalhost\n\tport\t8080";
+        let mut doc = parse_document(taml).unwrap();
+
+        assert_eq!(doc.get("server.host"), Some("localhost"));
+        doc.set("server.host", "example.com");
+        assert_eq!(doc.get("server.host"), Some("example.com"));
+        assert!(doc.to_string().contains("host\texample.com"));
+        assert!(doc.to_string().contains("port\t8080"));
+    }
+}
@@ -41,3 +41,23 @@ impl fmt::Display for TAMLError {
 }
 
 impl std::error::Error for TAMLError {}
+
+#[cfg(feature = "serde_support")]
+impl serde::de::Error for TAMLError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        TAMLError::new(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl serde::ser::Error for TAMLError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        TAMLError::new(msg.to_string())
+    }
+}
@@ -26,31 +26,70 @@
 //! ## Serializing to TAML
 //! 
 //! ```rust
-//! use taml::{Value, stringify};
-//! use std::collections::HashMap;
-//! 
-//! let mut data = HashMap::new();
+//! use taml::{Map, Value, stringify};
+//!
+//! let mut data = Map::new();
 //! data.insert("application".to_string(), Value::String("MyApp".to_string()));
 //! data.insert("version".to_string(), Value::String("1.0.0".to_string()));
 //! 
 //! let taml_text = stringify(&Value::Object(data));
 //! ```
+//!
+//! ## Serde support
+//!
+//! With the `serde_support` feature enabled, arbitrary types that derive
+//! `Serialize`/`Deserialize` can be read from and written to TAML directly,
+//! without going through `Value` by hand:
+//!
+//! ```rust,ignore
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     application: String,
+//!     version: String,
+//! }
+//!
+//! let cfg: Config = taml::from_str(taml_text)?;
+//! let text = taml::to_string(&cfg)?;
+//! ```
 
+mod datetime;
+#[cfg(feature = "serde_support")]
+mod de;
+mod document;
 mod error;
+mod macros;
 mod parser;
+#[cfg(feature = "serde_support")]
+mod ser;
 mod serializer;
+mod spanned;
 mod validator;
 mod value;
 
+pub use datetime::{Date, Datetime, Offset, Time};
+#[cfg(feature = "serde_support")]
+pub use de::{from_str, from_value, Deserializer};
+pub use document::{parse_document, DocEntry, Document};
 pub use error::{TAMLError, TAMLResult};
 pub use parser::{parse, parse_strict, ParseOptions};
-pub use serializer::{stringify, stringify_with_options, SerializeOptions};
+#[cfg(feature = "serde_support")]
+pub use ser::{to_string, to_value, Serializer};
+pub use serializer::{
+    stringify, stringify_to_writer, stringify_with_options, EmptyCollection, KeyOrder,
+    SerializeOptions,
+};
+pub use spanned::{parse_spanned, Span, Spanned, SpannedValue};
 pub use validator::{validate, ValidationError};
-pub use value::Value;
+pub use value::{Map, Value};
 
 /// Constants used in TAML format
 pub mod constants {
     pub const TAB: char = '\t';
     pub const NULL_VALUE: &str = "~";
     pub const EMPTY_STRING: &str = "\"\"";
+    /// Marks a line as an array element (rather than an object key), so
+    /// that element can itself be a full object subtree. See
+    /// [`crate::parse`]'s handling of arrays of objects.
+    pub const ARRAY_ITEM: &str = "-";
 }
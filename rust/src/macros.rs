@@ -0,0 +1,139 @@
+//! The `taml!` macro - build `Value` trees from a JSON-like literal
+//!
+//! Hand-assembling a `Value` tree is verbose: every object needs a
+//! `Map::new()` plus one `.insert(...)` per field, and every scalar needs
+//! wrapping in the right `Value` variant. `taml!` expands a literal,
+//! JSON-like syntax into exactly that code at compile time, the same way
+//! the TOML crate's `toml!` macro does for `toml::Value`.
+//!
+//! ```
+//! use taml::{taml, Value};
+//!
+//! let value = taml!({
+//!     application: "MyApp",
+//!     server: {
+//!         host: "localhost",
+//!         port: 8080,
+//!         active: true,
+//!     },
+//!     tags: ["web", "prod"],
+//!     nickname: null,
+//! });
+//!
+//! assert_eq!(value.as_object().unwrap().len(), 4);
+//! ```
+//!
+//! Scalars are inferred from the token's own type (string literal, integer,
+//! float, or bool) via [`Value::from`]; `null`/`~` map to [`Value::Null`].
+//! An array/object element that isn't a single literal, identifier, or
+//! bracketed group needs wrapping in parens, e.g. `(1 + 1)` - this mirrors
+//! the single-token-tree restriction of similar macros in other crates.
+
+/// Build a [`crate::Value`] from a JSON-like literal. See the [module-level
+/// docs](self) for syntax and examples.
+#[macro_export]
+macro_rules! taml {
+    ($($tt:tt)+) => {
+        $crate::taml_internal!($($tt)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! taml_internal {
+    (null) => {
+        $crate::Value::Null
+    };
+    (~) => {
+        $crate::Value::Null
+    };
+    ([$($tt:tt)*]) => {
+        $crate::Value::Array($crate::taml_internal_vec![$($tt)*])
+    };
+    ({$($tt:tt)*}) => {
+        $crate::Value::Object({
+            #[allow(unused_mut)]
+            let mut map = $crate::Map::new();
+            $crate::taml_internal_map!(map, $($tt)*);
+            map
+        })
+    };
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! taml_internal_vec {
+    () => {
+        Vec::new()
+    };
+    ($($val:tt),+ $(,)?) => {
+        vec![$($crate::taml_internal!($val)),+]
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! taml_internal_map {
+    ($map:ident $(,)?) => {};
+    ($map:ident, $key:ident : $val:tt $(, $($rest:tt)*)?) => {
+        $map.insert(stringify!($key).to_string(), $crate::taml_internal!($val));
+        $crate::taml_internal_map!($map, $($($rest)*)?);
+    };
+    ($map:ident, $key:literal : $val:tt $(, $($rest:tt)*)?) => {
+        $map.insert(($key).to_string(), $crate::taml_internal!($val));
+        $crate::taml_internal_map!($map, $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+
+    #[test]
+    fn test_taml_macro_builds_nested_value() {
+        let value = taml!({
+            application: "MyApp",
+            server: {
+                host: "localhost",
+                port: 8080,
+                active: true,
+            },
+            tags: ["web", "prod"],
+            nickname: null,
+        });
+
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.get("application"), Some(&Value::String("MyApp".to_string())));
+        assert_eq!(obj.get("nickname"), Some(&Value::Null));
+
+        let server = obj.get("server").unwrap().as_object().unwrap();
+        assert_eq!(server.get("port"), Some(&Value::Integer(8080)));
+        assert_eq!(server.get("active"), Some(&Value::Boolean(true)));
+
+        let tags = obj.get("tags").unwrap().as_array().unwrap();
+        assert_eq!(
+            tags,
+            &vec![
+                Value::String("web".to_string()),
+                Value::String("prod".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_taml_macro_matches_hand_built_value() {
+        let mut server = crate::Map::new();
+        server.insert("host".to_string(), Value::String("localhost".to_string()));
+
+        let mut expected = crate::Map::new();
+        expected.insert("server".to_string(), Value::Object(server));
+
+        assert_eq!(
+            taml!({ server: { host: "localhost" } }),
+            Value::Object(expected)
+        );
+    }
+}
@@ -1,9 +1,10 @@
 //! TAML Parser - Parse TAML formatted text into Value objects
 
-use crate::constants::{EMPTY_STRING, NULL_VALUE, TAB};
+use crate::constants::{ARRAY_ITEM, EMPTY_STRING, NULL_VALUE, TAB};
+use crate::datetime::Datetime;
 use crate::error::{TAMLError, TAMLResult};
-use crate::value::Value;
-use std::collections::HashMap;
+use crate::value::{Map, Value};
+use std::str::FromStr;
 
 /// Options for parsing TAML
 #[derive(Debug, Clone)]
@@ -41,10 +42,39 @@ pub fn parse_strict(text: &str) -> TAMLResult<Value> {
 
 /// Parse a TAML string with custom options
 pub fn parse_with_options(text: &str, options: ParseOptions) -> TAMLResult<Value> {
+    let entries = tokenize(text, &options)?;
+
+    // Second pass: Build tree
+    if entries.is_empty() {
+        return Ok(Value::Object(Map::new()));
+    }
+
+    let (value, _) = build_tree(&entries, 0, entries.len(), 0, options.type_conversion)?;
+    Ok(value)
+}
+
+/// An entry produced by the first parsing pass: a key, its (optional) raw
+/// value text, and its source location, used to build both the plain
+/// `Value` tree and the span-annotated tree in [`crate::spanned`].
+pub(crate) struct Entry {
+    pub(crate) level: usize,
+    pub(crate) key: String,
+    pub(crate) raw_value: Option<String>,
+    /// 1-based source line the entry appeared on.
+    pub(crate) line: usize,
+    /// 0-based column the key starts at (i.e. the indentation width).
+    pub(crate) key_col: usize,
+    /// 0-based column the value starts at, if this entry has a value.
+    pub(crate) value_col: Option<usize>,
+}
+
+/// First pass: tokenize source lines into [`Entry`] values, tracking each
+/// entry's source line/column so span-aware consumers don't need to
+/// re-scan the text.
+pub(crate) fn tokenize(text: &str, options: &ParseOptions) -> TAMLResult<Vec<Entry>> {
     let lines: Vec<&str> = text.lines().collect();
     let mut entries = Vec::new();
-    
-    // First pass: Parse lines into entries
+
     for (i, line) in lines.iter().enumerate() {
         let line_num = i + 1;
 
@@ -74,7 +104,7 @@ pub fn parse_with_options(text: &str, options: ParseOptions) -> TAMLResult<Value
 
         // Find key-value separator
         let tab_index = content.find(TAB);
-        let (key, raw_value) = if let Some(idx) = tab_index {
+        let (key, raw_value, value_col) = if let Some(idx) = tab_index {
             let key = &content[..idx];
             let value_start = idx + content[idx..].chars().take_while(|&c| c == TAB).count();
             let raw = if value_start < content.len() {
@@ -82,9 +112,9 @@ pub fn parse_with_options(text: &str, options: ParseOptions) -> TAMLResult<Value
             } else {
                 ""
             };
-            (key, Some(raw))
+            (key, Some(raw), Some(level + value_start))
         } else {
-            (content.trim_end(), None)
+            (content.trim_end(), None, None)
         };
 
         if key.is_empty() {
@@ -111,22 +141,13 @@ pub fn parse_with_options(text: &str, options: ParseOptions) -> TAMLResult<Value
             level,
             key: key.to_string(),
             raw_value: raw_value.map(|s| s.to_string()),
+            line: line_num,
+            key_col: level,
+            value_col,
         });
     }
 
-    // Second pass: Build tree
-    if entries.is_empty() {
-        return Ok(Value::Object(HashMap::new()));
-    }
-
-    let (value, _) = build_tree(&entries, 0, entries.len(), 0, options.type_conversion)?;
-    Ok(value)
-}
-
-struct Entry {
-    level: usize,
-    key: String,
-    raw_value: Option<String>,
+    Ok(entries)
 }
 
 fn build_tree(
@@ -136,15 +157,21 @@ fn build_tree(
     expected_level: usize,
     type_conversion: bool,
 ) -> TAMLResult<(Value, usize)> {
-    let mut result = HashMap::new();
+    let mut result = Map::new();
     let mut i = start;
 
     while i < end && entries[i].level >= expected_level {
         let entry = &entries[i];
-        
+
         if entry.level > expected_level {
-            i += 1;
-            continue;
+            // A line indented deeper than this level expects, with no
+            // enclosing key to attach it to (e.g. extra children under a
+            // scalar leaf). Silently stepping over it would drop data, so
+            // treat it as a structural error instead.
+            return Err(TAMLError::with_line(
+                format!("Unexpected indentation for key \"{}\"", entry.key),
+                entry.line,
+            ));
         }
 
         if let Some(ref raw) = entry.raw_value {
@@ -162,12 +189,19 @@ fn build_tree(
             let mut children_end = j;
             let mut has_key_value = false;
             let mut all_values_only = true;
-            
+            let mut has_array_items = false;
+            let mut stray_sibling: Option<&Entry> = None;
+
             while j < end && entries[j].level > expected_level {
                 if entries[j].level == child_level {
                     children_end = j + 1;
-                    if entries[j].raw_value.is_some() {
-                        has_key_value = true;
+                    if entries[j].key == ARRAY_ITEM {
+                        has_array_items = true;
+                    } else {
+                        stray_sibling.get_or_insert(&entries[j]);
+                        if entries[j].raw_value.is_some() {
+                            has_key_value = true;
+                        }
                     }
                     // Check if this child has its own children
                     if j + 1 < end && entries[j + 1].level > child_level {
@@ -176,10 +210,56 @@ fn build_tree(
                 }
                 j += 1;
             }
-            
-            // Determine if children form an array or object
-            if !has_key_value && all_values_only && children_start < children_end {
-                // It's an array
+
+            // An array-of-tables level (`-`-prefixed children) must be
+            // homogeneous: a sibling key that isn't itself an array item is
+            // ambiguous (is it a field of the enclosing object, or did the
+            // author mean to nest it inside the preceding item?) and would
+            // otherwise be silently dropped by the array-building loop
+            // below.
+            if has_array_items {
+                if let Some(stray) = stray_sibling {
+                    return Err(TAMLError::with_line(
+                        format!(
+                            "Key \"{}\" mixes array items (\"-\") with a regular key \"{}\" at the same level",
+                            entry.key, stray.key
+                        ),
+                        stray.line,
+                    ));
+                }
+            }
+
+            // Determine how to interpret the children
+            if has_array_items {
+                // Array whose elements are marked with ARRAY_ITEM, each of
+                // which may be a scalar (inline value) or a full object
+                // subtree (its own nested children).
+                let mut arr = Vec::new();
+                let mut k = children_start;
+                while k < j {
+                    if entries[k].level == child_level && entries[k].key == ARRAY_ITEM {
+                        if let Some(ref raw) = entries[k].raw_value {
+                            arr.push(convert_value(raw, type_conversion));
+                            k += 1;
+                        } else {
+                            let (item, next) =
+                                build_tree(entries, k + 1, j, child_level + 1, type_conversion)?;
+                            arr.push(item);
+                            k = next;
+                        }
+                    } else {
+                        // Stray siblings were rejected above, so this is a
+                        // line indented under a scalar item, which has no
+                        // children of its own.
+                        return Err(TAMLError::with_line(
+                            format!("Unexpected indentation for key \"{}\"", entries[k].key),
+                            entries[k].line,
+                        ));
+                    }
+                }
+                result.insert(entry.key.clone(), Value::Array(arr));
+            } else if !has_key_value && all_values_only && children_start < children_end {
+                // Shorthand array of bare keys, e.g. a list of feature names
                 let mut arr = Vec::new();
                 for entry in entries.iter().take(children_end).skip(children_start) {
                     if entry.level == child_level {
@@ -192,7 +272,7 @@ fn build_tree(
                 let (child_obj, _) = build_tree(entries, children_start, j, child_level, type_conversion)?;
                 result.insert(entry.key.clone(), child_obj);
             }
-            
+
             i = j;
         }
     }
@@ -222,6 +302,16 @@ fn convert_value(raw: &str, type_conversion: bool) -> Value {
         return Value::Boolean(false);
     }
 
+    // Try datetime (RFC 3339 date-time, a bare date, or a bare time). This
+    // runs before the numeric checks below, but only on strings that start
+    // with a digit and contain the `-`/`:` separators a datetime requires,
+    // so a plain integer like "2024" is never misread as a date.
+    if (raw.contains('-') || raw.contains(':')) && raw.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        if let Ok(dt) = Datetime::from_str(raw) {
+            return Value::Datetime(dt);
+        }
+    }
+
     // Try integer
     if let Ok(i) = raw.parse::<i64>() {
         return Value::Integer(i);
@@ -287,6 +377,59 @@ mod tests {
         assert_eq!(obj.get("float").unwrap().as_float(), Some(3.14));
     }
 
+    #[test]
+    fn test_parse_datetime() {
+        let taml = "created\t2024-01-02T03:04:05Z\nbirthday\t2024-01-02\nalarm\t07:30:00";
+        let result = parse(taml).unwrap();
+
+        let obj = result.as_object().unwrap();
+        assert!(obj.get("created").unwrap().is_datetime());
+        assert!(obj.get("birthday").unwrap().is_datetime());
+        assert!(obj.get("alarm").unwrap().is_datetime());
+    }
+
+    #[test]
+    fn test_numeric_looking_string_is_not_a_datetime() {
+        let taml = "year\t2024";
+        let result = parse(taml).unwrap();
+
+        let obj = result.as_object().unwrap();
+        assert_eq!(obj.get("year").unwrap().as_integer(), Some(2024));
+    }
+
+    #[test]
+    fn test_parse_array_of_objects() {
+        let taml = "servers\n\t-\n\t\thost\ta.example.com\n\t\tport\t80\n\t-\n\t\thost\tb.example.com\n\t\tport\t81";
+        let result = parse(taml).unwrap();
+
+        let obj = result.as_object().unwrap();
+        let servers = obj.get("servers").unwrap().as_array().unwrap();
+        assert_eq!(servers.len(), 2);
+
+        let first = servers[0].as_object().unwrap();
+        assert_eq!(first.get("host").unwrap().as_string(), Some("a.example.com"));
+        assert_eq!(first.get("port").unwrap().as_integer(), Some(80));
+
+        let second = servers[1].as_object().unwrap();
+        assert_eq!(second.get("host").unwrap().as_string(), Some("b.example.com"));
+        assert_eq!(second.get("port").unwrap().as_integer(), Some(81));
+    }
+
+    #[test]
+    fn test_parse_mixed_array_of_scalars_and_objects() {
+        let taml = "items\n\t-\tfirst\n\t-\n\t\tname\tsecond";
+        let result = parse(taml).unwrap();
+
+        let obj = result.as_object().unwrap();
+        let items = obj.get("items").unwrap().as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_string(), Some("first"));
+        assert_eq!(
+            items[1].as_object().unwrap().get("name").unwrap().as_string(),
+            Some("second")
+        );
+    }
+
     #[test]
     fn test_parse_nested_object() {
         let taml = "server\n\thost\tlocalhost\n\tport\t8080";
@@ -310,4 +453,22 @@ mod tests {
         assert_eq!(items[1].as_string(), Some("second"));
         assert_eq!(items[2].as_string(), Some("third"));
     }
+
+    #[test]
+    fn test_array_item_mixed_with_stray_key_is_an_error() {
+        let taml = "flags\n\t-\ttrue\n\tverbose\tfalse";
+        let err = parse(taml).unwrap_err();
+        assert!(err.to_string().contains("verbose"));
+    }
+
+    #[test]
+    fn test_orphaned_child_of_a_scalar_array_item_is_an_error() {
+        let taml = "-\tfoo\n\t\tbar\tbaz";
+        let err = parse(taml).unwrap_err();
+        assert!(err.to_string().contains("bar"));
+
+        let taml = "items\n\t-\tfoo\n\t\tbar\tbaz";
+        let err = parse(taml).unwrap_err();
+        assert!(err.to_string().contains("bar"));
+    }
 }
@@ -0,0 +1,420 @@
+//! Serde `Serializer` that turns an arbitrary `Serialize` type into a `Value`
+//!
+//! This is the write half of the serde integration: it lets callers do
+//! `taml::to_string(&my_struct)` instead of building a `Value` tree by hand.
+
+use serde::ser::{self, Serialize};
+
+use crate::error::{TAMLError, TAMLResult};
+use crate::value::{Map, Value};
+
+/// Serialize a `Serialize` type directly into TAML text.
+pub fn to_string<T>(value: &T) -> TAMLResult<String>
+where
+    T: Serialize,
+{
+    Ok(crate::stringify(&to_value(value)?))
+}
+
+/// Serialize a `Serialize` type into a `Value` tree.
+pub fn to_value<T>(value: &T) -> TAMLResult<Value>
+where
+    T: Serialize,
+{
+    value.serialize(Serializer)
+}
+
+/// A serde `Serializer` that builds a `Value` instead of emitting text directly.
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVec;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeMap;
+
+    fn serialize_bool(self, v: bool) -> TAMLResult<Value> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> TAMLResult<Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> TAMLResult<Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> TAMLResult<Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> TAMLResult<Value> {
+        Ok(Value::Integer(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> TAMLResult<Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u16(self, v: u16) -> TAMLResult<Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u32(self, v: u32) -> TAMLResult<Value> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u64(self, v: u64) -> TAMLResult<Value> {
+        i64::try_from(v)
+            .map(Value::Integer)
+            .map_err(|_| TAMLError::new("integer out of range for TAML"))
+    }
+
+    fn serialize_f32(self, v: f32) -> TAMLResult<Value> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> TAMLResult<Value> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> TAMLResult<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> TAMLResult<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> TAMLResult<Value> {
+        Ok(Value::Array(
+            v.iter().map(|b| Value::Integer(*b as i64)).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> TAMLResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> TAMLResult<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> TAMLResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> TAMLResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> TAMLResult<Value> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> TAMLResult<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> TAMLResult<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut obj = Map::new();
+        obj.insert(variant.to_string(), value.serialize(Serializer)?);
+        Ok(Value::Object(obj))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> TAMLResult<SerializeVec> {
+        Ok(SerializeVec {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> TAMLResult<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> TAMLResult<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> TAMLResult<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> TAMLResult<SerializeMap> {
+        Ok(SerializeMap {
+            entries: Map::new(),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> TAMLResult<SerializeMap> {
+        Ok(SerializeMap {
+            entries: Map::with_capacity(len),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> TAMLResult<SerializeMap> {
+        self.serialize_struct(_name, len)
+    }
+}
+
+/// Collects elements for `Value::Array`.
+pub struct SerializeVec {
+    elements: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.elements.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        Ok(Value::Array(self.elements))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVec {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Collects entries for `Value::Object`.
+pub struct SerializeMap {
+    entries: Map,
+    next_key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = key.serialize(Serializer)?;
+        self.next_key = Some(
+            key.as_string()
+                .map(|s| s.to_string())
+                .ok_or_else(|| TAMLError::new("TAML map keys must be strings"))?,
+        );
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| TAMLError::new("serialize_value called before serialize_key"))?;
+        self.entries.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        Ok(Value::Object(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.entries.insert(key.to_string(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        Ok(Value::Object(self.entries))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeMap {
+    type Ok = Value;
+    type Error = TAMLError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> TAMLResult<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> TAMLResult<Value> {
+        ser::SerializeStruct::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Server {
+        host: String,
+        port: u16,
+        active: bool,
+    }
+
+    #[derive(Serialize)]
+    struct Config {
+        application: String,
+        server: Server,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_to_value_serializes_nested_struct() {
+        let config = Config {
+            application: "MyApp".to_string(),
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+                active: true,
+            },
+            tags: vec!["web".to_string(), "prod".to_string()],
+        };
+
+        let mut server = Map::new();
+        server.insert("host".to_string(), Value::String("localhost".to_string()));
+        server.insert("port".to_string(), Value::Integer(8080));
+        server.insert("active".to_string(), Value::Boolean(true));
+        let mut expected = Map::new();
+        expected.insert("application".to_string(), Value::String("MyApp".to_string()));
+        expected.insert("server".to_string(), Value::Object(server));
+        expected.insert(
+            "tags".to_string(),
+            Value::Array(vec![
+                Value::String("web".to_string()),
+                Value::String("prod".to_string()),
+            ]),
+        );
+
+        assert_eq!(to_value(&config).unwrap(), Value::Object(expected));
+    }
+
+    #[test]
+    fn test_to_string_round_trips_through_from_str() {
+        let config = Config {
+            application: "MyApp".to_string(),
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+                active: true,
+            },
+            tags: vec!["web".to_string(), "prod".to_string()],
+        };
+
+        let taml = to_string(&config).unwrap();
+        let round_tripped: Value = crate::from_str(&taml).unwrap();
+
+        assert_eq!(round_tripped, to_value(&config).unwrap());
+    }
+
+    #[test]
+    fn test_u64_out_of_range_for_i64_is_a_descriptive_error() {
+        let err = to_value(&u64::MAX).unwrap_err();
+        assert!(err.to_string().contains("out of range"));
+    }
+}
@@ -1,13 +1,85 @@
 //! TAML Serializer - Serialize Value objects into TAML formatted text
 
-use crate::constants::{EMPTY_STRING, NULL_VALUE, TAB};
-use crate::value::Value;
+use std::io::{self, Write};
+
+use crate::constants::{ARRAY_ITEM, EMPTY_STRING, NULL_VALUE, TAB};
+use crate::value::{Map, Value};
+
+/// How to order an object's keys when serializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Emit keys in whatever order the underlying `Map` iterates.
+    AsStored,
+    /// Sort keys alphabetically, regardless of the underlying map type.
+    Alphabetical,
+}
+
+impl Default for KeyOrder {
+    // With `preserve_order`, `Map` iteration order is already the documented,
+    // deterministic parse/insertion order. Without it, `Map` is a `HashMap`,
+    // whose order carries no guarantee, so alphabetical is the only default
+    // that keeps `stringify` deterministic regardless of the feature.
+    #[cfg(feature = "preserve_order")]
+    fn default() -> Self {
+        KeyOrder::AsStored
+    }
+
+    #[cfg(not(feature = "preserve_order"))]
+    fn default() -> Self {
+        KeyOrder::Alphabetical
+    }
+}
+
+/// How to emit a nested object/array that has no entries.
+///
+/// TAML has no syntax of its own for "this key's value is an empty
+/// collection" - a key with no children is indistinguishable from a parent
+/// whose single child just happens to be missing. These options trade off
+/// between matching the crate's original (ambiguous) output and making the
+/// emptiness explicit at the cost of a line [`crate::parse`] doesn't
+/// specifically understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyCollection {
+    /// Emit the key on its own line with no children, exactly as earlier
+    /// versions of `stringify` always did.
+    #[default]
+    BareHeader,
+    /// Emit the key, then an explicit marker line one level deeper, so the
+    /// emptiness is visible in the output. Note that `parse` reads this
+    /// marker back as a one-element array holding the string `"~"`, not as
+    /// an empty collection.
+    ExplicitMarker,
+    /// Drop the key entirely rather than emit a header with no children.
+    OmitHeader,
+}
 
 /// Options for serializing TAML
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SerializeOptions {
     /// Starting indentation level
     pub indent_level: usize,
+    /// The string repeated `level` times to indent each nesting level.
+    /// Defaults to a single tab, matching the format's own convention.
+    pub indent_unit: String,
+    /// Whether the output ends with a trailing newline.
+    pub trailing_newline: bool,
+    /// How to order each object's keys. See [`KeyOrder`].
+    pub key_order: KeyOrder,
+    /// How to emit a nested object/array with no entries. See
+    /// [`EmptyCollection`].
+    pub empty_collection: EmptyCollection,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        SerializeOptions {
+            indent_level: 0,
+            indent_unit: TAB.to_string(),
+            trailing_newline: false,
+            key_order: KeyOrder::default(),
+            empty_collection: EmptyCollection::default(),
+        }
+    }
 }
 
 /// Serialize a Value to TAML format
@@ -17,70 +89,129 @@ pub fn stringify(value: &Value) -> String {
 
 /// Serialize a Value to TAML format with custom options
 pub fn stringify_with_options(value: &Value, options: SerializeOptions) -> String {
-    let mut lines = Vec::new();
-    serialize_value(value, &mut lines, options.indent_level);
-    lines.join("\n")
+    let mut buf = Vec::new();
+    stringify_to_writer(value, options, &mut buf).expect("writing to a Vec<u8> cannot fail");
+    String::from_utf8(buf).expect("serializer only ever writes valid UTF-8")
 }
 
-fn serialize_value(value: &Value, lines: &mut Vec<String>, level: usize) {
-    match value {
-        Value::Object(obj) => {
-            serialize_object(obj, lines, level);
-        }
-        Value::Array(arr) => {
-            serialize_array(arr, lines, level);
+/// Serialize a Value to TAML format, writing lines directly to `writer` as
+/// they're produced instead of buffering the whole document in memory first.
+pub fn stringify_to_writer<W: Write>(
+    value: &Value,
+    options: SerializeOptions,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut emitter = Emitter {
+        writer,
+        options: &options,
+        first: true,
+    };
+    emitter.serialize_value(value, options.indent_level)?;
+    if options.trailing_newline {
+        emitter.writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+struct Emitter<'a, W: Write> {
+    writer: &'a mut W,
+    options: &'a SerializeOptions,
+    first: bool,
+}
+
+impl<'a, W: Write> Emitter<'a, W> {
+    fn emit_line(&mut self, line: &str) -> io::Result<()> {
+        if !self.first {
+            self.writer.write_all(b"\n")?;
         }
-        _ => {
+        self.first = false;
+        self.writer.write_all(line.as_bytes())
+    }
+
+    fn serialize_value(&mut self, value: &Value, level: usize) -> io::Result<()> {
+        match value {
+            Value::Object(obj) => self.serialize_object(obj, level),
+            Value::Array(arr) => self.serialize_array(arr, level),
             // Leaf values should not be serialized directly at root
-            lines.push(value_to_string(value));
+            _ => self.emit_line(&value_to_string(value)),
         }
     }
-}
 
-fn serialize_object(
-    obj: &std::collections::HashMap<String, Value>,
-    lines: &mut Vec<String>,
-    level: usize,
-) {
-    let indent = TAB.to_string().repeat(level);
+    fn serialize_object(&mut self, obj: &Map, level: usize) -> io::Result<()> {
+        let indent = self.options.indent_unit.repeat(level);
 
-    for (key, value) in obj {
-        match value {
-            Value::Object(nested_obj) => {
-                lines.push(format!("{}{}", indent, key));
-                serialize_object(nested_obj, lines, level + 1);
+        for (key, value) in ordered_entries(obj, self.options.key_order) {
+            match value {
+                Value::Object(nested) if nested.is_empty() => self.emit_empty(&indent, key)?,
+                Value::Array(arr) if arr.is_empty() => self.emit_empty(&indent, key)?,
+                Value::Object(nested) => {
+                    self.emit_line(&format!("{}{}", indent, key))?;
+                    self.serialize_object(nested, level + 1)?;
+                }
+                Value::Array(arr) => {
+                    self.emit_line(&format!("{}{}", indent, key))?;
+                    self.serialize_array(arr, level + 1)?;
+                }
+                _ => {
+                    let val_str = value_to_string(value);
+                    self.emit_line(&format!("{}{}\t{}", indent, key, val_str))?;
+                }
             }
-            Value::Array(arr) => {
-                lines.push(format!("{}{}", indent, key));
-                serialize_array(arr, lines, level + 1);
-            }
-            _ => {
-                let val_str = value_to_string(value);
-                lines.push(format!("{}{}\t{}", indent, key, val_str));
+        }
+        Ok(())
+    }
+
+    fn emit_empty(&mut self, indent: &str, key: &str) -> io::Result<()> {
+        match self.options.empty_collection {
+            EmptyCollection::OmitHeader => Ok(()),
+            EmptyCollection::BareHeader => self.emit_line(&format!("{}{}", indent, key)),
+            EmptyCollection::ExplicitMarker => {
+                self.emit_line(&format!("{}{}", indent, key))?;
+                self.emit_line(&format!("{}{}{}", indent, self.options.indent_unit, NULL_VALUE))
             }
         }
     }
-}
 
-fn serialize_array(arr: &[Value], lines: &mut Vec<String>, level: usize) {
-    let indent = TAB.to_string().repeat(level);
+    fn serialize_array(&mut self, arr: &[Value], level: usize) -> io::Result<()> {
+        let indent = self.options.indent_unit.repeat(level);
 
-    for item in arr {
-        match item {
-            Value::Object(obj) => {
-                serialize_object(obj, lines, level);
-            }
-            Value::Array(nested_arr) => {
-                serialize_array(nested_arr, lines, level);
-            }
-            _ => {
-                let val_str = value_to_string(item);
-                lines.push(format!("{}{}", indent, val_str));
+        // An array holding any object elements needs every element marked with
+        // ARRAY_ITEM so the parser can tell items apart from a nested object's
+        // own keys; a purely-scalar array keeps the plain bare-value shorthand.
+        let needs_item_marker = arr.iter().any(|item| matches!(item, Value::Object(_)));
+
+        for item in arr {
+            match item {
+                Value::Object(obj) => {
+                    self.emit_line(&format!("{}{}", indent, ARRAY_ITEM))?;
+                    self.serialize_object(obj, level + 1)?;
+                }
+                Value::Array(nested_arr) => {
+                    self.serialize_array(nested_arr, level)?;
+                }
+                _ if needs_item_marker => {
+                    let val_str = value_to_string(item);
+                    self.emit_line(&format!("{}{}\t{}", indent, ARRAY_ITEM, val_str))?;
+                }
+                _ => {
+                    let val_str = value_to_string(item);
+                    self.emit_line(&format!("{}{}", indent, val_str))?;
+                }
             }
         }
+        Ok(())
     }
 }
 
+/// Entries of `obj` in the order `order` asks for. See [`KeyOrder`].
+fn ordered_entries(obj: &Map, order: KeyOrder) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = obj.iter().collect();
+    if order == KeyOrder::Alphabetical {
+        entries.sort_by_key(|(k, _)| *k);
+    }
+    entries
+}
+
 fn value_to_string(value: &Value) -> String {
     match value {
         Value::Null => NULL_VALUE.to_string(),
@@ -89,6 +220,7 @@ fn value_to_string(value: &Value) -> String {
         Value::Integer(i) => i.to_string(),
         Value::Float(f) => f.to_string(),
         Value::Boolean(b) => b.to_string(),
+        Value::Datetime(dt) => dt.to_string(),
         Value::Array(_) | Value::Object(_) => {
             // These should not be converted to string directly
             String::new()
@@ -99,69 +231,113 @@ fn value_to_string(value: &Value) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use crate::value::Map;
 
     #[test]
     fn test_stringify_simple() {
-        let mut obj = HashMap::new();
+        let mut obj = Map::new();
         obj.insert("key".to_string(), Value::String("value".to_string()));
-        
+
         let result = stringify(&Value::Object(obj));
         assert_eq!(result, "key\tvalue");
     }
 
     #[test]
     fn test_stringify_null() {
-        let mut obj = HashMap::new();
+        let mut obj = Map::new();
         obj.insert("key".to_string(), Value::Null);
-        
+
         let result = stringify(&Value::Object(obj));
         assert_eq!(result, "key\t~");
     }
 
     #[test]
     fn test_stringify_empty_string() {
-        let mut obj = HashMap::new();
+        let mut obj = Map::new();
         obj.insert("key".to_string(), Value::String(String::new()));
-        
+
         let result = stringify(&Value::Object(obj));
         assert_eq!(result, "key\t\"\"");
     }
 
     #[test]
     fn test_stringify_boolean() {
-        let mut obj = HashMap::new();
+        let mut obj = Map::new();
         obj.insert("enabled".to_string(), Value::Boolean(true));
         obj.insert("disabled".to_string(), Value::Boolean(false));
-        
+
         let result = stringify(&Value::Object(obj));
         assert!(result.contains("true") || result.contains("false"));
     }
 
     #[test]
     fn test_stringify_numbers() {
-        let mut obj = HashMap::new();
+        let mut obj = Map::new();
         obj.insert("integer".to_string(), Value::Integer(42));
         obj.insert("float".to_string(), Value::Float(3.14));
-        
+
         let result = stringify(&Value::Object(obj));
         assert!(result.contains("42") || result.contains("3.14"));
     }
 
+    #[test]
+    fn test_stringify_datetime() {
+        let taml = "created\t2024-01-02T03:04:05Z";
+        let parsed = crate::parse(taml).unwrap();
+
+        let result = stringify(&parsed);
+        assert_eq!(result, taml);
+    }
+
+    #[test]
+    fn test_stringify_array_of_objects_roundtrips() {
+        let taml = "servers\n\t-\n\t\thost\ta.example.com\n\t\tport\t80\n\t-\n\t\thost\tb.example.com\n\t\tport\t81";
+        let parsed = crate::parse(taml).unwrap();
+
+        let serialized = stringify(&parsed);
+        let reparsed = crate::parse(&serialized).unwrap();
+        assert_eq!(parsed, reparsed);
+    }
+
     #[test]
     fn test_stringify_nested_object() {
-        let mut server = HashMap::new();
+        let mut server = Map::new();
         server.insert("host".to_string(), Value::String("localhost".to_string()));
         server.insert("port".to_string(), Value::Integer(8080));
-        
-        let mut obj = HashMap::new();
+
+        let mut obj = Map::new();
         obj.insert("server".to_string(), Value::Object(server));
-        
+
         let result = stringify(&Value::Object(obj));
         assert!(result.contains("server"));
         assert!(result.contains("host\tlocalhost") || result.contains("localhost"));
     }
 
+    // Without `preserve_order`, `Map` is a `HashMap`, so two objects built by
+    // inserting the same keys in a different order must still serialize
+    // identically for `stringify` output to be usable in diffs/golden tests.
+    // With `preserve_order` this isn't meaningful: insertion order *is* the
+    // guarantee, so a different insertion order is a genuinely different
+    // document and is expected to serialize differently.
+    #[test]
+    #[cfg(not(feature = "preserve_order"))]
+    fn test_stringify_is_deterministic_regardless_of_key_insertion_order() {
+        let mut forward = Map::new();
+        forward.insert("alpha".to_string(), Value::Integer(1));
+        forward.insert("beta".to_string(), Value::Integer(2));
+        forward.insert("gamma".to_string(), Value::Integer(3));
+
+        let mut backward = Map::new();
+        backward.insert("gamma".to_string(), Value::Integer(3));
+        backward.insert("beta".to_string(), Value::Integer(2));
+        backward.insert("alpha".to_string(), Value::Integer(1));
+
+        assert_eq!(
+            stringify(&Value::Object(forward)),
+            stringify(&Value::Object(backward))
+        );
+    }
+
     #[test]
     fn test_stringify_array() {
         let items = vec![
@@ -169,14 +345,90 @@ mod tests {
             Value::String("second".to_string()),
             Value::String("third".to_string()),
         ];
-        
-        let mut obj = HashMap::new();
+
+        let mut obj = Map::new();
         obj.insert("items".to_string(), Value::Array(items));
-        
+
         let result = stringify(&Value::Object(obj));
         assert!(result.contains("items"));
         assert!(result.contains("first"));
         assert!(result.contains("second"));
         assert!(result.contains("third"));
     }
+
+    #[test]
+    fn test_stringify_to_writer_matches_stringify() {
+        let mut obj = Map::new();
+        obj.insert("key".to_string(), Value::String("value".to_string()));
+        let value = Value::Object(obj);
+
+        let mut buf = Vec::new();
+        stringify_to_writer(&value, SerializeOptions::default(), &mut buf).unwrap();
+
+        assert_eq!(String::from_utf8(buf).unwrap(), stringify(&value));
+    }
+
+    #[test]
+    fn test_trailing_newline_option() {
+        let mut obj = Map::new();
+        obj.insert("key".to_string(), Value::String("value".to_string()));
+
+        let options = SerializeOptions {
+            trailing_newline: true,
+            ..SerializeOptions::default()
+        };
+        let result = stringify_with_options(&Value::Object(obj), options);
+        assert_eq!(result, "key\tvalue\n");
+    }
+
+    #[test]
+    fn test_custom_indent_unit() {
+        let mut server = Map::new();
+        server.insert("host".to_string(), Value::String("localhost".to_string()));
+        let mut obj = Map::new();
+        obj.insert("server".to_string(), Value::Object(server));
+
+        let options = SerializeOptions {
+            indent_unit: "    ".to_string(),
+            ..SerializeOptions::default()
+        };
+        let result = stringify_with_options(&Value::Object(obj), options);
+        assert_eq!(result, "server\n    host\tlocalhost");
+    }
+
+    #[test]
+    fn test_empty_collection_bare_header_is_the_default() {
+        let mut obj = Map::new();
+        obj.insert("tags".to_string(), Value::Array(Vec::new()));
+
+        let result = stringify(&Value::Object(obj));
+        assert_eq!(result, "tags");
+    }
+
+    #[test]
+    fn test_empty_collection_explicit_marker() {
+        let mut obj = Map::new();
+        obj.insert("tags".to_string(), Value::Array(Vec::new()));
+
+        let options = SerializeOptions {
+            empty_collection: EmptyCollection::ExplicitMarker,
+            ..SerializeOptions::default()
+        };
+        let result = stringify_with_options(&Value::Object(obj), options);
+        assert_eq!(result, "tags\n\t~");
+    }
+
+    #[test]
+    fn test_empty_collection_omit_header() {
+        let mut obj = Map::new();
+        obj.insert("tags".to_string(), Value::Array(Vec::new()));
+        obj.insert("name".to_string(), Value::String("example".to_string()));
+
+        let options = SerializeOptions {
+            empty_collection: EmptyCollection::OmitHeader,
+            ..SerializeOptions::default()
+        };
+        let result = stringify_with_options(&Value::Object(obj), options);
+        assert_eq!(result, "name\texample");
+    }
 }
@@ -0,0 +1,454 @@
+//! Span-annotated parsing, for diagnostics that need to point at source text
+//!
+//! `Value` carries no source location, so a validator or editor built on top
+//! of `parse` can't say *where* a bad value came from beyond what
+//! [`crate::validate`] already reports per-line. `parse_spanned` re-parses
+//! the document into a tree of [`Spanned`] values that each know the
+//! line/column range of the key and value they came from.
+
+use crate::datetime::Datetime;
+use crate::error::TAMLResult;
+use crate::parser::{self, ParseOptions};
+
+/// A source location. Lines are 1-based and inclusive: a single-line key or
+/// value has `end_line == start_line`. Columns are 0-based; for a single-line
+/// key or value `end_col` is one past its last character, while a span that
+/// covers a whole object or array has `end_col` 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+/// A value annotated with the [`Span`] of source text it was parsed from.
+///
+/// `Spanned<T>` derefs to `&T`/`&mut T`, so it can usually be used wherever
+/// `T` is expected. With the `serde_support` feature it's also transparent
+/// to serde: `Serialize`/`Deserialize` just delegate to `T`, ignoring the
+/// span, so a `Spanned<T>` field on a struct deserialized through
+/// [`crate::from_str`] behaves exactly like a plain `T` field — the span is
+/// only populated by [`parse_spanned`] itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    value: T,
+    span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// The span of source text this value came from.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The wrapped value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwrap into the underlying value, discarding the span.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> std::ops::DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T: serde::Serialize> serde::Serialize for Spanned<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Spanned<T> {
+    /// Spans only exist once [`parse_spanned`] has walked the source text,
+    /// so a `Spanned<T>` reached through a generic serde `Deserializer`
+    /// (e.g. [`crate::from_str`]) gets a default, zeroed [`Span`].
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Spanned {
+            value: T::deserialize(deserializer)?,
+            span: Span::default(),
+        })
+    }
+}
+
+/// Mirrors [`crate::Value`], but every key and nested value carries its
+/// source [`Span`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpannedValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Null,
+    Datetime(Datetime),
+    Array(Vec<Spanned<SpannedValue>>),
+    Object(Vec<(Spanned<String>, Spanned<SpannedValue>)>),
+}
+
+/// Parse a TAML string into a span-annotated tree.
+pub fn parse_spanned(text: &str) -> TAMLResult<Spanned<SpannedValue>> {
+    let options = ParseOptions::default();
+    let entries = parser::tokenize(text, &options)?;
+
+    let root_span = Span {
+        start_line: 1,
+        start_col: 0,
+        end_line: entries.last().map(|e| e.line).unwrap_or(1),
+        end_col: 0,
+    };
+
+    let (object, _) = build_tree(&entries, 0, entries.len(), 0)?;
+    Ok(Spanned {
+        value: SpannedValue::Object(object),
+        span: root_span,
+    })
+}
+
+type SpannedEntries = Vec<(Spanned<String>, Spanned<SpannedValue>)>;
+
+/// Mirrors [`parser::build_tree`]'s handling of bare-value shorthand arrays
+/// and `ARRAY_ITEM` (array-of-tables) children, so `parse_spanned` produces
+/// the same shapes [`crate::parse`] does instead of flattening every child
+/// list into an `Object`.
+fn build_tree(
+    entries: &[parser::Entry],
+    start: usize,
+    end: usize,
+    expected_level: usize,
+) -> TAMLResult<(SpannedEntries, usize)> {
+    use crate::constants::ARRAY_ITEM;
+    use crate::error::TAMLError;
+
+    let mut result = Vec::new();
+    let mut i = start;
+
+    while i < end && entries[i].level >= expected_level {
+        let entry = &entries[i];
+
+        if entry.level > expected_level {
+            i += 1;
+            continue;
+        }
+
+        let key_span = Span {
+            start_line: entry.line,
+            start_col: entry.key_col,
+            end_line: entry.line,
+            end_col: entry.key_col + entry.key.chars().count(),
+        };
+        let key = Spanned {
+            value: entry.key.clone(),
+            span: key_span,
+        };
+
+        if let Some(ref raw) = entry.raw_value {
+            let value_col = entry.value_col.unwrap_or(entry.key_col);
+            let value_span = Span {
+                start_line: entry.line,
+                start_col: value_col,
+                end_line: entry.line,
+                end_col: value_col + raw.chars().count(),
+            };
+            let value = Spanned {
+                value: convert_spanned(raw),
+                span: value_span,
+            };
+            result.push((key, value));
+            i += 1;
+        } else {
+            let child_level = expected_level + 1;
+            let mut j = i + 1;
+
+            let children_start = j;
+            let mut children_end = j;
+            let mut has_key_value = false;
+            let mut all_values_only = true;
+            let mut has_array_items = false;
+            let mut stray_sibling: Option<&parser::Entry> = None;
+
+            while j < end && entries[j].level > expected_level {
+                if entries[j].level == child_level {
+                    children_end = j + 1;
+                    if entries[j].key == ARRAY_ITEM {
+                        has_array_items = true;
+                    } else {
+                        stray_sibling.get_or_insert(&entries[j]);
+                        if entries[j].raw_value.is_some() {
+                            has_key_value = true;
+                        }
+                    }
+                    if j + 1 < end && entries[j + 1].level > child_level {
+                        all_values_only = false;
+                    }
+                }
+                j += 1;
+            }
+
+            if has_array_items {
+                if let Some(stray) = stray_sibling {
+                    return Err(TAMLError::with_line(
+                        format!(
+                            "Key \"{}\" mixes array items (\"-\") with a regular key \"{}\" at the same level",
+                            entry.key, stray.key
+                        ),
+                        stray.line,
+                    ));
+                }
+
+                // Array whose elements are marked with ARRAY_ITEM, each of
+                // which may be a scalar (inline value) or a full object
+                // subtree.
+                let mut arr = Vec::new();
+                let mut k = children_start;
+                while k < j {
+                    if entries[k].level == child_level && entries[k].key == ARRAY_ITEM {
+                        if let Some(ref raw) = entries[k].raw_value {
+                            let value_col = entries[k].value_col.unwrap_or(entries[k].key_col);
+                            arr.push(Spanned {
+                                value: convert_spanned(raw),
+                                span: Span {
+                                    start_line: entries[k].line,
+                                    start_col: value_col,
+                                    end_line: entries[k].line,
+                                    end_col: value_col + raw.chars().count(),
+                                },
+                            });
+                            k += 1;
+                        } else {
+                            let (item, next) = build_tree(entries, k + 1, j, child_level + 1)?;
+                            let end_line = item
+                                .last()
+                                .map(|(_, v)| v.span.end_line)
+                                .unwrap_or(entries[k].line);
+                            arr.push(Spanned {
+                                value: SpannedValue::Object(item),
+                                span: Span {
+                                    start_line: entries[k].line,
+                                    start_col: entries[k].key_col,
+                                    end_line,
+                                    end_col: 0,
+                                },
+                            });
+                            k = next;
+                        }
+                    } else {
+                        return Err(TAMLError::with_line(
+                            format!("Unexpected indentation for key \"{}\"", entries[k].key),
+                            entries[k].line,
+                        ));
+                    }
+                }
+                let end_line = arr.last().map(|v| v.span.end_line).unwrap_or(entry.line);
+                let value = Spanned {
+                    value: SpannedValue::Array(arr),
+                    span: Span {
+                        start_line: entry.line,
+                        start_col: entry.key_col,
+                        end_line,
+                        end_col: 0,
+                    },
+                };
+                result.push((key, value));
+            } else if !has_key_value && all_values_only && children_start < children_end {
+                // Shorthand array of bare keys, e.g. a list of feature names.
+                let mut arr = Vec::new();
+                for child in entries.iter().take(children_end).skip(children_start) {
+                    if child.level == child_level {
+                        arr.push(Spanned {
+                            value: SpannedValue::String(child.key.clone()),
+                            span: Span {
+                                start_line: child.line,
+                                start_col: child.key_col,
+                                end_line: child.line,
+                                end_col: child.key_col + child.key.chars().count(),
+                            },
+                        });
+                    }
+                }
+                let end_line = arr.last().map(|v| v.span.end_line).unwrap_or(entry.line);
+                let value = Spanned {
+                    value: SpannedValue::Array(arr),
+                    span: Span {
+                        start_line: entry.line,
+                        start_col: entry.key_col,
+                        end_line,
+                        end_col: 0,
+                    },
+                };
+                result.push((key, value));
+            } else {
+                let (children, _) = build_tree(entries, children_start, j, child_level)?;
+
+                let end_line = children
+                    .last()
+                    .map(|(_, v)| v.span.end_line)
+                    .unwrap_or(entry.line);
+                let value_span = Span {
+                    start_line: entry.line,
+                    start_col: entry.key_col,
+                    end_line,
+                    end_col: 0,
+                };
+                let value = Spanned {
+                    value: SpannedValue::Object(children),
+                    span: value_span,
+                };
+                result.push((key, value));
+            }
+            i = j;
+        }
+    }
+
+    Ok((result, i))
+}
+
+fn convert_spanned(raw: &str) -> SpannedValue {
+    use crate::constants::{EMPTY_STRING, NULL_VALUE};
+    use std::str::FromStr;
+
+    if raw == NULL_VALUE {
+        return SpannedValue::Null;
+    }
+    if raw == EMPTY_STRING {
+        return SpannedValue::String(String::new());
+    }
+    if raw == "true" {
+        return SpannedValue::Boolean(true);
+    }
+    if raw == "false" {
+        return SpannedValue::Boolean(false);
+    }
+    if (raw.contains('-') || raw.contains(':')) && raw.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        if let Ok(dt) = Datetime::from_str(raw) {
+            return SpannedValue::Datetime(dt);
+        }
+    }
+    if let Ok(i) = raw.parse::<i64>() {
+        return SpannedValue::Integer(i);
+    }
+    if let Ok(f) = raw.parse::<f64>() {
+        return SpannedValue::Float(f);
+    }
+    SpannedValue::String(raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spanned_derefs_to_inner_value() {
+        let spanned = Spanned {
+            value: "application".to_string(),
+            span: Span::default(),
+        };
+        assert_eq!(spanned.len(), 11);
+        assert_eq!(&*spanned, "application");
+    }
+
+    #[test]
+    #[cfg(feature = "serde_support")]
+    fn test_spanned_is_transparent_to_serde() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            application: Spanned<String>,
+        }
+
+        let config: Config = crate::from_str("application\tMyApp").unwrap();
+        assert_eq!(config.application.value(), "MyApp");
+    }
+
+    #[test]
+    fn test_spans_point_at_source_lines() {
+        let taml = "application\tMyApp\nserver\n\thost\tlocalhost";
+        let spanned = parse_spanned(taml).unwrap();
+
+        let SpannedValue::Object(entries) = spanned.value() else {
+            panic!("root should be an object");
+        };
+
+        let (app_key, app_value) = &entries[0];
+        assert_eq!(app_key.value(), "application");
+        assert_eq!(app_value.span().start_line, 1);
+
+        let (server_key, server_value) = &entries[1];
+        assert_eq!(server_key.value(), "server");
+        let SpannedValue::Object(server_entries) = server_value.value() else {
+            panic!("server should be an object");
+        };
+        let (host_key, host_value) = &server_entries[0];
+        assert_eq!(host_key.value(), "host");
+        assert_eq!(host_value.span().start_line, 3);
+    }
+
+    #[test]
+    fn test_bare_value_shorthand_parses_as_array() {
+        let taml = "items\n\tfirst\n\tsecond\n\tthird";
+        let spanned = parse_spanned(taml).unwrap();
+
+        let SpannedValue::Object(entries) = spanned.value() else {
+            panic!("root should be an object");
+        };
+        let (items_key, items_value) = &entries[0];
+        assert_eq!(items_key.value(), "items");
+
+        let SpannedValue::Array(items) = items_value.value() else {
+            panic!("items should be an array, not an object");
+        };
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].value(), &SpannedValue::String("first".to_string()));
+        assert_eq!(items[1].value(), &SpannedValue::String("second".to_string()));
+        assert_eq!(items[2].value(), &SpannedValue::String("third".to_string()));
+    }
+
+    #[test]
+    fn test_array_of_tables_parses_as_array_of_objects() {
+        let taml = "servers\n\t-\n\t\thost\ta.example.com\n\t-\n\t\thost\tb.example.com";
+        let spanned = parse_spanned(taml).unwrap();
+
+        let SpannedValue::Object(entries) = spanned.value() else {
+            panic!("root should be an object");
+        };
+        let (servers_key, servers_value) = &entries[0];
+        assert_eq!(servers_key.value(), "servers");
+
+        let SpannedValue::Array(servers) = servers_value.value() else {
+            panic!("servers should be an array, not an object");
+        };
+        assert_eq!(servers.len(), 2);
+        let SpannedValue::Object(first) = servers[0].value() else {
+            panic!("array item should be an object");
+        };
+        assert_eq!(first[0].0.value(), "host");
+        assert_eq!(first[0].1.value(), &SpannedValue::String("a.example.com".to_string()));
+    }
+
+    #[test]
+    fn test_array_item_mixed_with_stray_key_is_an_error() {
+        let taml = "flags\n\t-\ttrue\n\tverbose\tfalse";
+        let err = parse_spanned(taml).unwrap_err();
+        assert!(err.to_string().contains("verbose"));
+    }
+}
@@ -1,6 +1,20 @@
 //! TAML Value type representation
 
-use std::collections::HashMap;
+use crate::datetime::Datetime;
+
+/// The map type backing `Value::Object`.
+///
+/// With the `preserve_order` feature enabled this is an [`indexmap::IndexMap`],
+/// so keys are iterated in the order they were inserted (i.e. the order they
+/// appeared in the source document). Without the feature it falls back to
+/// `std::collections::HashMap`, which does not make any ordering guarantee.
+#[cfg(feature = "preserve_order")]
+pub type Map = indexmap::IndexMap<String, Value>;
+
+/// The map type backing `Value::Object`. See the `preserve_order` feature
+/// for an insertion-order-preserving alternative.
+#[cfg(not(feature = "preserve_order"))]
+pub type Map = std::collections::HashMap<String, Value>;
 
 /// Represents a TAML value
 #[derive(Debug, Clone, PartialEq)]
@@ -15,10 +29,12 @@ pub enum Value {
     Boolean(bool),
     /// Null value (represented as ~ in TAML)
     Null,
+    /// A date, time, or date-time value (e.g. `2024-01-02T03:04:05Z`)
+    Datetime(Datetime),
     /// Array of values
     Array(Vec<Value>),
     /// Object (key-value pairs)
-    Object(HashMap<String, Value>),
+    Object(Map),
 }
 
 impl Value {
@@ -47,6 +63,11 @@ impl Value {
         matches!(self, Value::Null)
     }
 
+    /// Returns true if the value is a datetime
+    pub fn is_datetime(&self) -> bool {
+        matches!(self, Value::Datetime(_))
+    }
+
     /// Returns true if the value is an array
     pub fn is_array(&self) -> bool {
         matches!(self, Value::Array(_))
@@ -89,6 +110,14 @@ impl Value {
         }
     }
 
+    /// Get as datetime if the value is a datetime
+    pub fn as_datetime(&self) -> Option<&Datetime> {
+        match self {
+            Value::Datetime(dt) => Some(dt),
+            _ => None,
+        }
+    }
+
     /// Get as array if the value is an array
     pub fn as_array(&self) -> Option<&Vec<Value>> {
         match self {
@@ -98,7 +127,7 @@ impl Value {
     }
 
     /// Get as object if the value is an object
-    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+    pub fn as_object(&self) -> Option<&Map> {
         match self {
             Value::Object(obj) => Some(obj),
             _ => None,
@@ -106,8 +135,44 @@ impl Value {
     }
 }
 
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Boolean(b)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Integer(i)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(f: f64) -> Self {
+        Value::Float(f)
+    }
+}
+
+impl From<Datetime> for Value {
+    fn from(dt: Datetime) -> Self {
+        Value::Datetime(dt)
+    }
+}
+
 #[cfg(feature = "serde_support")]
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[cfg(feature = "serde_support")]
 impl Serialize for Value {
@@ -121,6 +186,7 @@ impl Serialize for Value {
             Value::Float(f) => serializer.serialize_f64(*f),
             Value::Boolean(b) => serializer.serialize_bool(*b),
             Value::Null => serializer.serialize_none(),
+            Value::Datetime(dt) => serializer.serialize_str(&dt.to_string()),
             Value::Array(arr) => {
                 use serde::ser::SerializeSeq;
                 let mut seq = serializer.serialize_seq(Some(arr.len()))?;
@@ -140,3 +206,89 @@ impl Serialize for Value {
         }
     }
 }
+
+#[cfg(feature = "serde_support")]
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ValueVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a valid TAML value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(Value::Boolean(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(Value::Integer(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i64::try_from(v)
+                    .map(Value::Integer)
+                    .map_err(|_| E::custom("integer out of range for TAML"))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(Value::Float(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Value::String(v.to_string()))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+                Ok(Value::String(v))
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Deserialize::deserialize(deserializer)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut arr = Vec::new();
+                while let Some(element) = seq.next_element()? {
+                    arr.push(element);
+                }
+                Ok(Value::Array(arr))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut obj = Map::new();
+                while let Some((key, value)) = map.next_entry::<String, Value>()? {
+                    obj.insert(key, value);
+                }
+                Ok(Value::Object(obj))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}